@@ -1,48 +1,236 @@
-#![feature(int_log)]
-use std::mem::size_of;
+#![cfg_attr(not(test), no_std)]
 
-const BLOCK_SIZE_BYTES: usize = size_of::<usize>() * 8;
+extern crate alloc;
 
-/// usize-ary tree with Logk(n) + 1 layers, where 'k' is the size of usize in bits, and 'n' is the ammount of items.
-/// 
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem::size_of;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Shl, Sub};
+
+/// A fixed-width unsigned integer usable as the block word of a [`SuccTree`]
+///
+/// Implemented for `u8`, `u16`, `u32`, `u64` and `u128`, so callers can pick
+/// the fan-out/word width that fits their universe
+pub trait Word:
+    Copy
+    + Eq
+    + BitOr<Output = Self>
+    + BitOrAssign
+    + BitAnd<Output = Self>
+    + BitAndAssign
+    + Not<Output = Self>
+    + Sub<Output = Self>
+    + Shl<u32, Output = Self>
+{
+    /// Number of bits in the word
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn count_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+    fn leading_zeros(self) -> u32;
+
+    /// Append the word to `out` as little-endian bytes
+    fn write_le_bytes(self, out: &mut Vec<u8>);
+
+    /// Read a word from exactly `BITS / 8` little-endian bytes
+    fn read_le_bytes(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_word {
+    ($ty:ty) => {
+        impl Word for $ty
+        {
+            const BITS: u32 = <$ty>::BITS;
+            const ZERO: $ty = 0;
+            const ONE: $ty = 1;
+
+            fn count_ones(self) -> u32
+            {
+                <$ty>::count_ones(self)
+            }
+
+            fn trailing_zeros(self) -> u32
+            {
+                <$ty>::trailing_zeros(self)
+            }
+
+            fn leading_zeros(self) -> u32
+            {
+                <$ty>::leading_zeros(self)
+            }
+
+            fn write_le_bytes(self, out: &mut Vec<u8>)
+            {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn read_le_bytes(bytes: &[u8]) -> $ty
+            {
+                let mut buf = [0u8; (<$ty>::BITS / 8) as usize];
+                buf.copy_from_slice(bytes);
+                <$ty>::from_le_bytes(buf)
+            }
+        }
+    };
+}
+
+impl_word!(u8);
+impl_word!(u16);
+impl_word!(u32);
+impl_word!(u64);
+impl_word!(u128);
+
+/// Word counts for every layer of a tree holding `size` items, from the leaves
+/// up to the always-empty top layer
+///
+/// Shared by [`SuccTree::new`] and [`SuccTree::from_bytes`] so the latter can
+/// check how many words a `size` header implies without allocating the
+/// layers themselves
+fn plan_layers(size: usize, block_size: usize) -> Vec<usize>
+{
+    let mut layer_sizes = Vec::new();
+    let mut bits = size;
+    loop
+    {
+        let words = bits.div_ceil(block_size).max(1);
+        layer_sizes.push(words);
+        if words == 1
+        {
+            break;
+        }
+        bits = words;
+    }
+    layer_sizes.push(1);
+    layer_sizes
+}
+
+/// `W`-ary tree with Logk(n) + 1 layers, where 'k' is the size of `W` in bits, and 'n' is the ammount of items.
+///
 /// Each layer is n/k.pow(layer) bits long with every node being 1 bit.
-pub struct SuccTree
+pub struct SuccTree<W: Word>
+{
+    size: usize,
+    tree: Vec<Vec<W>>
+}
+
+/// Error returned by [`SuccTree::from_bytes`] when a buffer isn't a valid
+/// serialized tree
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError
 {
-    tree: Vec<Vec<usize>>
+    /// The buffer is too short to even contain the `size` header
+    MissingHeader,
+    /// The buffer's length doesn't match what the `size` header says it
+    /// should be, once decoded
+    LengthMismatch { expected: usize, actual: usize },
+    /// The `size` header doesn't fit in this platform's `usize`
+    InvalidSize,
 }
 
-impl SuccTree
+impl<W: Word> SuccTree<W>
 {
     /// Create a new tree with `size` items
-    /// 
+    ///
     /// # Example
     /// ```
     /// # use succtree::SuccTree;
-    /// let mut tree = SuccTree::new(1000000);      // n = 1000000, k = usize (u64)
+    /// let mut tree = SuccTree::<u64>::new(1000000);      // n = 1000000, k = u64
     /// tree.insert(5);
     /// ```
-    pub fn new(size: usize) -> SuccTree
+    pub fn new(size: usize) -> SuccTree<W>
     {
-        let block_size_f64 = BLOCK_SIZE_BYTES as f64;
-        let layers = (size as f64).log(block_size_f64).ceil() as usize + 1;
-        let mut tree = Vec::with_capacity(layers);
-        for i in 0..layers
+        let layer_sizes = plan_layers(size, Self::block_size());
+        let tree = layer_sizes.into_iter().map(|words| vec![W::ZERO; words]).collect();
+        SuccTree { size, tree }
+    }
+
+    /// Build a tree of `size` from a batch of indices in a single bottom-up pass
+    ///
+    /// Unlike repeated [`SuccTree::insert`], which re-walks the parent chain for
+    /// every item, this ORs every index into layer 0 directly and then derives
+    /// each summary layer from the layer below it once
+    pub fn from_indices(size: usize, iter: impl IntoIterator<Item = usize>) -> SuccTree<W>
+    {
+        let mut tree = SuccTree::new(size);
+        let block_size = Self::block_size();
+        for item in iter
         {
-            let layer_size = ((size as f64) / block_size_f64.powi(i as i32)).ceil();
-            let layer_size = (layer_size / block_size_f64).ceil() as usize;
-            tree.push(vec![0usize; layer_size])
+            tree.tree[0][item / block_size] |= W::ONE << (item % block_size) as u32;
         }
-        SuccTree {  tree }
+        tree.rebuild_summary_layers();
+        tree
+    }
+
+    /// Build a tree of `size` from an already-sorted slice of indices
+    pub fn from_sorted_slice(size: usize, items: &[usize]) -> SuccTree<W>
+    {
+        SuccTree::from_indices(size, items.iter().copied())
+    }
+
+    /// Serialize the tree to a compact byte buffer: an 8-byte little-endian
+    /// `size` header followed by every layer's words, also little-endian
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let bytes_per_word = (W::BITS / 8) as usize;
+        let word_count: usize = self.tree.iter().map(Vec::len).sum();
+        let mut bytes = Vec::with_capacity(size_of::<u64>() + word_count * bytes_per_word);
+        bytes.extend_from_slice(&(self.size as u64).to_le_bytes());
+        for layer in &self.tree
+        {
+            for &word in layer
+            {
+                word.write_le_bytes(&mut bytes);
+            }
+        }
+        bytes
+    }
+
+    /// Deserialize a tree previously written by [`SuccTree::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<SuccTree<W>, ParseError>
+    {
+        let header_len = size_of::<u64>();
+        if bytes.len() < header_len
+        {
+            return Err(ParseError::MissingHeader);
+        }
+        let size = u64::from_le_bytes(bytes[..header_len].try_into().unwrap());
+        let size = usize::try_from(size).map_err(|_| ParseError::InvalidSize)?;
+
+        // Work out how many words this `size` implies before allocating any of
+        // the tree's layers, so a buffer too short to back a huge `size` header
+        // is rejected as a `LengthMismatch` rather than forcing a huge allocation
+        let bytes_per_word = (W::BITS / 8) as usize;
+        let word_count: usize = plan_layers(size, Self::block_size()).iter().sum();
+        let expected_len = header_len + word_count * bytes_per_word;
+        if bytes.len() != expected_len
+        {
+            return Err(ParseError::LengthMismatch { expected: expected_len, actual: bytes.len() });
+        }
+
+        let mut tree = SuccTree::new(size);
+        let mut cursor = header_len;
+        for layer in tree.tree.iter_mut()
+        {
+            for word in layer.iter_mut()
+            {
+                *word = W::read_le_bytes(&bytes[cursor..cursor + bytes_per_word]);
+                cursor += bytes_per_word;
+            }
+        }
+        Ok(tree)
     }
 
     /// Set a bit at `item` position
     pub fn insert(&mut self, mut item: usize)
     {
+        let block_size = Self::block_size();
         for layer in 0..=(self.tree.len() - 2)
         {
             // set the bit
-            self.tree[layer][item / BLOCK_SIZE_BYTES] |= 1 << item % BLOCK_SIZE_BYTES;
-            item = SuccTree::move_up_layer(item);
+            self.tree[layer][item / block_size] |= W::ONE << (item % block_size) as u32;
+            item = SuccTree::<W>::move_up_layer(item);
             if self.is_parent_set(layer + 1, item)
             {
                 break;
@@ -53,15 +241,16 @@ impl SuccTree
     /// Unset a bit at `item` position
     pub fn delete(&mut self, mut item: usize)
     {
+        let block_size = Self::block_size();
         for layer in 0..=(self.tree.len() - 2)
         {
             // unset the bit
-            self.tree[layer][item / BLOCK_SIZE_BYTES] &= !(1 << item % BLOCK_SIZE_BYTES);
+            self.tree[layer][item / block_size] &= !(W::ONE << (item % block_size) as u32);
             if self.is_any_sibling_set(layer, item)
             {
                 break;
             }
-            item = SuccTree::move_up_layer(item);
+            item = SuccTree::<W>::move_up_layer(item);
         }
     }
 
@@ -77,7 +266,7 @@ impl SuccTree
         }
         while next_sibling == 0 && layer < self.tree.len() - 1
         {
-            item = SuccTree::move_up_layer(item);
+            item = SuccTree::<W>::move_up_layer(item);
             layer += 1;
             next_sibling = self.greater_sibling_in_block(layer, item);
         }
@@ -85,9 +274,9 @@ impl SuccTree
         {
             return None;
         }
-        while layer > 0 
+        while layer > 0
         {
-            item = SuccTree::move_down(next_sibling);
+            item = SuccTree::<W>::move_down(next_sibling);
             layer -= 1;
             next_sibling = self.first_item_set_in_block(layer, item);
         }
@@ -96,98 +285,429 @@ impl SuccTree
 
 
     /// Returns the range of siblings with lower inclusive and upper exclusive
-    /// 
+    ///
     /// # Example
     /// ```
     /// # use succtree::SuccTree;
-    /// let mut tree = SuccTree::new(64);
+    /// let mut tree = SuccTree::<u64>::new(64);
     /// for i in 0..64
     /// {
     ///    tree.insert(i);
     /// }
     /// assert_eq!(vec![5, 6, 7, 8, 9], tree.rquery(5, 10));
     /// ```
-    pub fn rquery(&self, mut lower: usize, upper: usize) -> Vec<usize>
+    pub fn rquery(&self, lower: usize, upper: usize) -> Vec<usize>
+    {
+        self.range(lower, upper).collect()
+    }
+
+    /// Iterate every set bit in the tree, in ascending order
+    pub fn iter(&self) -> Iter<'_, W>
+    {
+        Iter { tree: self, front: None, back: None, lower: 0, upper: None, exhausted: false }
+    }
+
+    /// Iterate the set bits in `[lower, upper)`
+    ///
+    /// # Example
+    /// ```
+    /// # use succtree::SuccTree;
+    /// let mut tree = SuccTree::<u64>::new(64);
+    /// for i in 0..64
+    /// {
+    ///    tree.insert(i);
+    /// }
+    /// assert_eq!(vec![5, 6, 7, 8, 9], tree.range(5, 10).collect::<Vec<_>>());
+    /// ```
+    pub fn range(&self, lower: usize, upper: usize) -> Iter<'_, W>
+    {
+        Iter { tree: self, front: None, back: None, lower, upper: Some(upper), exhausted: lower >= upper }
+    }
+
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.tree[self.tree.len() - 2][0] == W::ZERO
+    }
+
+    pub fn min(&self) -> Option<usize>
+    {
+        if self.tree[0][0] & W::ONE != W::ZERO
+        {
+            Some(0)
+        }
+        else
+        {
+            self.successor(0)
+        }
+    }
+
+    /// Find the previous value before `item`
+    pub fn predecessor(&self, mut item: usize) -> Option<usize>
+    {
+        if item == 0
+        {
+            return None;
+        }
+        let mut layer = 0;
+        let mut prev_sibling = self.lesser_sibling_in_block(layer, item);
+        while prev_sibling.is_none() && layer < self.tree.len() - 1
+        {
+            item = SuccTree::<W>::move_up_layer(item);
+            layer += 1;
+            prev_sibling = self.lesser_sibling_in_block(layer, item);
+        }
+        let mut prev_sibling = prev_sibling?;
+        while layer > 0
+        {
+            item = SuccTree::<W>::move_down(prev_sibling);
+            layer -= 1;
+            prev_sibling = self.last_item_set_in_block(layer, item);
+        }
+        Some(prev_sibling)
+    }
+
+    pub fn max(&self) -> Option<usize>
     {
-        let mut result = Vec::new();
-        if (self.tree[0][lower / BLOCK_SIZE_BYTES] & 1) == 1
+        let top = self.tree.len() - 2;
+        if self.tree[top][0] == W::ZERO
         {
-            result.push(lower);
+            return None;
         }
-        while let Some(next_sibling) = self.successor(lower)
+        let mut layer = top;
+        let mut item = self.last_item_set_in_block(top, 0);
+        while layer > 0
         {
-            if next_sibling >= upper
+            item = SuccTree::<W>::move_down(item);
+            layer -= 1;
+            item = self.last_item_set_in_block(layer, item);
+        }
+        Some(item)
+    }
+
+    /// Total number of set bits in the tree
+    pub fn count(&self) -> usize
+    {
+        self.tree[0].iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Number of set bits strictly below `item`
+    pub fn rank(&self, item: usize) -> usize
+    {
+        let block_size = Self::block_size();
+        let word_index = item / block_size;
+        if word_index >= self.tree[0].len()
+        {
+            return self.count();
+        }
+        let mut rank: usize = self.tree[0][..word_index].iter().map(|word| word.count_ones() as usize).sum();
+        let mut mask = W::ZERO;
+        for i in 0..(item % block_size)
+        {
+            mask |= W::ONE << i as u32;
+        }
+        rank += (self.tree[0][word_index] & mask).count_ones() as usize;
+        rank
+    }
+
+    /// Position of the `k`-th smallest set bit (0-indexed)
+    pub fn select(&self, mut k: usize) -> Option<usize>
+    {
+        let block_size = Self::block_size();
+        for (word_index, word) in self.tree[0].iter().enumerate()
+        {
+            let ones = word.count_ones() as usize;
+            if k < ones
             {
-                break;
+                let mut word = *word;
+                for _ in 0..k
+                {
+                    word &= word - W::ONE;
+                }
+                return Some(word_index * block_size + word.trailing_zeros() as usize);
             }
-            result.push(next_sibling);
-            lower = next_sibling;
+            k -= ones;
         }
-        result
+        None
     }
 
+    /// Set-union with `other`, consuming `self`
+    ///
+    /// Both trees must have been built with the same `size`
+    pub fn union(mut self, other: &SuccTree<W>) -> SuccTree<W>
+    {
+        self.union_with(other);
+        self
+    }
 
-    pub fn is_empty(&self) -> bool
+    /// Set-union `other` into `self` in place
+    ///
+    /// A parent bit meaning "some child is set" is preserved under OR, so
+    /// every layer can be combined word-wise without rebuilding summaries
+    pub fn union_with(&mut self, other: &SuccTree<W>)
     {
-        self.tree[self.tree.len() - 2][0] == 0
+        self.assert_same_shape(other);
+        for (layer, other_layer) in self.tree.iter_mut().zip(other.tree.iter())
+        {
+            for (word, &other_word) in layer.iter_mut().zip(other_layer.iter())
+            {
+                *word |= other_word;
+            }
+        }
     }
 
-    pub fn min(&self) -> Option<usize>
+    /// Set-intersection with `other`, consuming `self`
+    ///
+    /// Both trees must have been built with the same `size`
+    pub fn intersection(mut self, other: &SuccTree<W>) -> SuccTree<W>
     {
-        if self.tree[0][0] & 1 != 0
+        self.intersection_with(other);
+        self
+    }
+
+    /// Set-intersection `other` into `self` in place
+    ///
+    /// Intersection can clear children without clearing their parent, so
+    /// layer 0 is AND-ed directly and every summary layer is rebuilt
+    pub fn intersection_with(&mut self, other: &SuccTree<W>)
+    {
+        self.assert_same_shape(other);
+        for (word, &other_word) in self.tree[0].iter_mut().zip(other.tree[0].iter())
         {
-            Some(0)
+            *word &= other_word;
         }
-        else
+        self.rebuild_summary_layers();
+    }
+
+    /// Set-difference with `other` (items in `self` but not in `other`),
+    /// consuming `self`
+    ///
+    /// Both trees must have been built with the same `size`
+    pub fn difference(mut self, other: &SuccTree<W>) -> SuccTree<W>
+    {
+        self.difference_with(other);
+        self
+    }
+
+    /// Set-difference `other` out of `self` in place
+    ///
+    /// Like [`SuccTree::intersection_with`], this can clear children without
+    /// clearing their parent, so layer 0 is AND-NOT-ed directly and every
+    /// summary layer is rebuilt
+    pub fn difference_with(&mut self, other: &SuccTree<W>)
+    {
+        self.assert_same_shape(other);
+        for (word, &other_word) in self.tree[0].iter_mut().zip(other.tree[0].iter())
         {
-            self.successor(0)
+            *word &= !other_word;
         }
+        self.rebuild_summary_layers();
+    }
+
+    /// Panics in debug builds if `self` and `other` don't share the same
+    /// per-layer word counts
+    fn assert_same_shape(&self, other: &SuccTree<W>)
+    {
+        debug_assert!(
+            self.tree.iter().map(Vec::len).eq(other.tree.iter().map(Vec::len)),
+            "trees must be built with the same size to combine"
+        );
     }
 
     /// Get the first set bit in a block
     fn first_item_set_in_block(&self, layer: usize, block: usize) -> usize
     {
-        block + self.tree[layer][block / BLOCK_SIZE_BYTES].trailing_zeros() as usize
+        block + self.tree[layer][block / Self::block_size()].trailing_zeros() as usize
+    }
+
+    /// Get the last set bit in a block
+    fn last_item_set_in_block(&self, layer: usize, block: usize) -> usize
+    {
+        block + Self::block_size() - 1 - self.tree[layer][block / Self::block_size()].leading_zeros() as usize
     }
 
     /// Get the next set bit in a block or return 0
     fn greater_sibling_in_block(&self, layer: usize, item: usize) -> usize
     {
-        let mut value = self.tree[layer][item / BLOCK_SIZE_BYTES];
-        let mut mask = 0;
-        for i in 0..=(item % BLOCK_SIZE_BYTES)
+        let block_size = Self::block_size();
+        let mut value = self.tree[layer][item / block_size];
+        let mut mask = W::ZERO;
+        for i in 0..=(item % block_size)
         {
-            mask |= 1 << i;
+            mask |= W::ONE << i as u32;
         }
         value &= !mask;
-        if value == 0
+        if value == W::ZERO
         {
             return 0;
         }
         // Go to the first index of a block and add trailing zeros
-        ((item / BLOCK_SIZE_BYTES) * BLOCK_SIZE_BYTES) + value.trailing_zeros() as usize
-    } 
+        ((item / block_size) * block_size) + value.trailing_zeros() as usize
+    }
+
+    /// Get the previous set bit in a block, or `None` if there isn't one
+    fn lesser_sibling_in_block(&self, layer: usize, item: usize) -> Option<usize>
+    {
+        let block_size = Self::block_size();
+        let mut value = self.tree[layer][item / block_size];
+        let mut mask = W::ZERO;
+        for i in (item % block_size)..block_size
+        {
+            mask |= W::ONE << i as u32;
+        }
+        value &= !mask;
+        if value == W::ZERO
+        {
+            return None;
+        }
+        // Go to the first index of a block and add the position of the highest set bit
+        Some(((item / block_size) * block_size) + (block_size - 1 - value.leading_zeros() as usize))
+    }
 
     /// In an items block, is there any other bit set
     fn is_any_sibling_set(&self, layer: usize, item: usize) -> bool
     {
-        self.tree[layer][item / BLOCK_SIZE_BYTES] != 0
+        self.tree[layer][item / Self::block_size()] != W::ZERO
     }
 
     /// Is the parent of the current block set
     fn is_parent_set(&self, layer: usize, item: usize) -> bool
     {
-        self.tree[layer][item / BLOCK_SIZE_BYTES] & 1 << item % BLOCK_SIZE_BYTES != 0
+        let block_size = Self::block_size();
+        self.tree[layer][item / block_size] & (W::ONE << (item % block_size) as u32) != W::ZERO
     }
 
     fn move_up_layer(item: usize) -> usize
     {
-        item / BLOCK_SIZE_BYTES
+        item / Self::block_size()
     }
 
     fn move_down(item: usize) -> usize
     {
-        item * BLOCK_SIZE_BYTES
+        item * Self::block_size()
+    }
+
+    /// Is the bit at `item` set in the bottom layer
+    fn is_set(&self, item: usize) -> bool
+    {
+        let block_size = Self::block_size();
+        self.tree[0][item / block_size] & (W::ONE << (item % block_size) as u32) != W::ZERO
+    }
+
+    /// Recompute every summary layer above layer 0 from scratch: a parent bit
+    /// is set iff its child word is nonzero
+    fn rebuild_summary_layers(&mut self)
+    {
+        let block_size = Self::block_size();
+        for layer in 1..=(self.tree.len() - 2)
+        {
+            let mut next = vec![W::ZERO; self.tree[layer].len()];
+            for (j, &word) in self.tree[layer - 1].iter().enumerate()
+            {
+                if word != W::ZERO
+                {
+                    next[j / block_size] |= W::ONE << (j % block_size) as u32;
+                }
+            }
+            self.tree[layer] = next;
+        }
+    }
+
+    /// Number of bits in a single block word
+    fn block_size() -> usize
+    {
+        W::BITS as usize
+    }
+}
+
+/// A lazy iterator over the set bits of a [`SuccTree`], produced by
+/// [`SuccTree::iter`] and [`SuccTree::range`]
+pub struct Iter<'a, W: Word>
+{
+    tree: &'a SuccTree<W>,
+    front: Option<usize>,
+    back: Option<usize>,
+    lower: usize,
+    upper: Option<usize>,
+    exhausted: bool,
+}
+
+impl<'a, W: Word> Iterator for Iter<'a, W>
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize>
+    {
+        if self.exhausted
+        {
+            return None;
+        }
+        let candidate = match self.front
+        {
+            None if self.tree.is_set(self.lower) => Some(self.lower),
+            None => self.tree.successor(self.lower),
+            Some(last) => self.tree.successor(last),
+        };
+        match candidate
+        {
+            Some(value) if self.upper.is_none_or(|upper| value < upper)
+                && self.back.is_none_or(|back| value < back) =>
+            {
+                self.front = Some(value);
+                Some(value)
+            }
+            _ =>
+            {
+                self.exhausted = true;
+                None
+            }
+        }
+    }
+}
+
+impl<'a, W: Word> DoubleEndedIterator for Iter<'a, W>
+{
+    fn next_back(&mut self) -> Option<usize>
+    {
+        if self.exhausted
+        {
+            return None;
+        }
+        let candidate = match self.back
+        {
+            None => match self.upper
+            {
+                Some(0) => None,
+                Some(upper) if self.tree.is_set(upper - 1) => Some(upper - 1),
+                Some(upper) => self.tree.predecessor(upper),
+                None => self.tree.max(),
+            },
+            Some(last) => self.tree.predecessor(last),
+        };
+        match candidate
+        {
+            Some(value) if value >= self.lower && self.front.is_none_or(|front| value > front) =>
+            {
+                self.back = Some(value);
+                Some(value)
+            }
+            _ =>
+            {
+                self.exhausted = true;
+                None
+            }
+        }
+    }
+}
+
+impl<'a, W: Word> IntoIterator for &'a SuccTree<W>
+{
+    type Item = usize;
+    type IntoIter = Iter<'a, W>;
+
+    fn into_iter(self) -> Iter<'a, W>
+    {
+        self.iter()
     }
 }
 
@@ -196,30 +716,32 @@ mod tests {
 
     use super::*;
 
+    type Tree = SuccTree<u64>;
+
     #[test]
     fn test_move_up()
     {
-        assert_eq!(0, SuccTree::move_up_layer(10));
-        assert_eq!(1, SuccTree::move_up_layer(64));
-        assert_eq!(1, SuccTree::move_up_layer(127));
-        assert_eq!(2, SuccTree::move_up_layer(128));
-        assert_eq!(3, SuccTree::move_up_layer(192));
+        assert_eq!(0, Tree::move_up_layer(10));
+        assert_eq!(1, Tree::move_up_layer(64));
+        assert_eq!(1, Tree::move_up_layer(127));
+        assert_eq!(2, Tree::move_up_layer(128));
+        assert_eq!(3, Tree::move_up_layer(192));
     }
 
     #[test]
     fn test_move_down()
     {
-        assert_eq!(0, SuccTree::move_down(0));
-        assert_eq!(64, SuccTree::move_down(1));
-        assert_eq!(128, SuccTree::move_down(2));
-        assert_eq!(192, SuccTree::move_down(3));
-        assert_eq!(256, SuccTree::move_down(4));
+        assert_eq!(0, Tree::move_down(0));
+        assert_eq!(64, Tree::move_down(1));
+        assert_eq!(128, Tree::move_down(2));
+        assert_eq!(192, Tree::move_down(3));
+        assert_eq!(256, Tree::move_down(4));
     }
 
     #[test]
     fn test_rquery()
     {
-        let mut tree = SuccTree::new(1000000);
+        let mut tree = Tree::new(1000000);
         let mut r = Vec::with_capacity(1000000);
         for i in 0..999999
         {
@@ -232,7 +754,7 @@ mod tests {
     #[test]
     fn test_even_rquery()
     {
-        let mut tree = SuccTree::new(1000000);
+        let mut tree = Tree::new(1000000);
         let mut r = Vec::with_capacity(1000000);
         for i in (0..999999).step_by(2)
         {
@@ -245,7 +767,7 @@ mod tests {
     #[test]
     fn test_uneven_rquery()
     {
-        let mut tree = SuccTree::new(1000000);
+        let mut tree = Tree::new(1000000);
         let mut r = Vec::with_capacity(1000000);
         for i in (1..999999).step_by(2)
         {
@@ -255,10 +777,214 @@ mod tests {
         assert_eq!(r, tree.rquery(0, 1000000));
     }
 
+    #[test]
+    fn test_iter()
+    {
+        let mut tree = Tree::new(1000000);
+        let inserted = [5, 9, 30, 64, 65, 99, 99999, 100000];
+        for i in inserted
+        {
+            tree.insert(i);
+        }
+        assert_eq!(inserted.to_vec(), tree.iter().collect::<Vec<_>>());
+        assert_eq!(inserted.to_vec(), (&tree).into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_iter_rev()
+    {
+        let mut tree = Tree::new(1000000);
+        let inserted = [5, 9, 30, 64, 65, 99, 99999, 100000];
+        for i in inserted
+        {
+            tree.insert(i);
+        }
+        let mut expected = inserted.to_vec();
+        expected.reverse();
+        assert_eq!(expected, tree.iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_range()
+    {
+        let mut tree = Tree::new(64);
+        for i in 0..64
+        {
+            tree.insert(i);
+        }
+        assert_eq!(vec![5, 6, 7, 8, 9], tree.range(5, 10).collect::<Vec<_>>());
+        assert_eq!(vec![9, 8, 7, 6, 5], tree.range(5, 10).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_count()
+    {
+        let mut tree = Tree::new(1000000);
+        assert_eq!(0, tree.count());
+        tree.insert(5);
+        tree.insert(9);
+        tree.insert(100000);
+        assert_eq!(3, tree.count());
+        tree.delete(9);
+        assert_eq!(2, tree.count());
+    }
+
+    #[test]
+    fn test_rank()
+    {
+        let mut tree = Tree::new(1000000);
+        let inserted = [5, 9, 30, 64, 65, 99, 99999, 100000];
+        for i in inserted
+        {
+            tree.insert(i);
+        }
+        assert_eq!(0, tree.rank(5));
+        assert_eq!(1, tree.rank(9));
+        assert_eq!(3, tree.rank(31));
+        assert_eq!(5, tree.rank(99));
+        assert_eq!(6, tree.rank(99999));
+        assert_eq!(7, tree.rank(100000));
+        assert_eq!(8, tree.rank(100001));
+        // 1,000,000 is an exact multiple of the u64 word width, so this lands
+        // word_index right on the boundary one past the last word of layer 0
+        assert_eq!(tree.count(), tree.rank(1000000));
+    }
+
+    #[test]
+    fn test_select()
+    {
+        let mut tree = Tree::new(1000000);
+        let inserted = [5, 9, 30, 64, 65, 99, 99999, 100000];
+        for i in inserted
+        {
+            tree.insert(i);
+        }
+        for (k, item) in inserted.iter().enumerate()
+        {
+            assert_eq!(Some(*item), tree.select(k));
+        }
+        assert_eq!(None, tree.select(inserted.len()));
+    }
+
+    #[test]
+    fn test_from_indices()
+    {
+        let inserted = [5, 9, 30, 64, 65, 99, 99999, 100000];
+        let tree = Tree::from_indices(1000000, inserted);
+        assert_eq!(inserted.to_vec(), tree.iter().collect::<Vec<_>>());
+
+        let mut expected = Tree::new(1000000);
+        for i in inserted
+        {
+            expected.insert(i);
+        }
+        assert_eq!(expected.tree, tree.tree);
+    }
+
+    #[test]
+    fn test_from_sorted_slice()
+    {
+        let inserted = [5, 9, 30, 64, 65, 99, 99999, 100000];
+        let tree = Tree::from_sorted_slice(1000000, &inserted);
+        assert_eq!(inserted.to_vec(), tree.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_union()
+    {
+        let a = Tree::from_indices(1000000, [5, 9, 64]);
+        let b = Tree::from_indices(1000000, [9, 30, 99999]);
+        let union = a.union(&b);
+        assert_eq!(vec![5, 9, 30, 64, 99999], union.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_intersection()
+    {
+        let a = Tree::from_indices(1000000, [5, 9, 64]);
+        let b = Tree::from_indices(1000000, [9, 30, 99999]);
+        let intersection = a.intersection(&b);
+        assert_eq!(vec![9], intersection.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_difference()
+    {
+        let a = Tree::from_indices(1000000, [5, 9, 64]);
+        let b = Tree::from_indices(1000000, [9, 30, 99999]);
+        let difference = a.difference(&b);
+        assert_eq!(vec![5, 64], difference.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_union_with()
+    {
+        let mut a = Tree::from_indices(1000000, [5, 9, 64]);
+        let b = Tree::from_indices(1000000, [9, 30, 99999]);
+        a.union_with(&b);
+        assert_eq!(vec![5, 9, 30, 64, 99999], a.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_to_from_bytes()
+    {
+        let tree = Tree::from_indices(1000000, [5, 9, 30, 64, 65, 99, 99999, 100000]);
+        let bytes = tree.to_bytes();
+        let decoded = Tree::from_bytes(&bytes).unwrap();
+        assert_eq!(tree.size, decoded.size);
+        assert_eq!(tree.tree, decoded.tree);
+    }
+
+    #[test]
+    fn test_from_bytes_missing_header()
+    {
+        assert!(matches!(Tree::from_bytes(&[0u8; 3]), Err(ParseError::MissingHeader)));
+    }
+
+    #[test]
+    fn test_from_bytes_length_mismatch()
+    {
+        let tree = Tree::from_indices(1000000, [5, 9]);
+        let mut bytes = tree.to_bytes();
+        bytes.push(0);
+        assert!(matches!(Tree::from_bytes(&bytes), Err(ParseError::LengthMismatch { .. })));
+        bytes.pop();
+        bytes.pop();
+        assert!(matches!(Tree::from_bytes(&bytes), Err(ParseError::LengthMismatch { .. })));
+    }
+
+    #[test]
+    fn test_from_bytes_bogus_size_header_does_not_panic()
+    {
+        // A `size` this large would, prior to validating the buffer length,
+        // have overflowed while computing the layer sizes or tried to
+        // allocate an absurd amount of memory. It should just error out.
+        assert!(Tree::from_bytes(&u64::MAX.to_le_bytes()).is_err());
+        assert!(SuccTree::<u8>::from_bytes(&u64::MAX.to_le_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_non_u64_word()
+    {
+        let mut tree = SuccTree::<u8>::from_indices(1000, [5, 9, 30, 64, 65, 99, 500]);
+        assert_eq!(vec![5, 9, 30, 64, 65, 99, 500], tree.iter().collect::<Vec<_>>());
+        assert_eq!(4, tree.rank(65));
+        assert_eq!(Some(30), tree.select(2));
+        assert_eq!(7, tree.count());
+
+        tree.insert(10);
+        assert_eq!(Some(10), tree.successor(9));
+
+        let bytes = tree.to_bytes();
+        let decoded = SuccTree::<u8>::from_bytes(&bytes).unwrap();
+        assert_eq!(tree.size, decoded.size);
+        assert_eq!(tree.tree, decoded.tree);
+    }
+
     #[test]
     fn test_succ()
     {
-        let mut tree = SuccTree::new(1000000);
+        let mut tree = Tree::new(1000000);
         tree.insert(5);
         assert_eq!(None, tree.successor(5));
         tree.insert(9);
@@ -270,16 +996,52 @@ mod tests {
         tree.insert(100000);
         assert_eq!(Some(9), tree.successor(5));
         assert_eq!(Some(30), tree.successor(9));
-        assert_eq!(Some(64), tree.successor(30));        
+        assert_eq!(Some(64), tree.successor(30));
         assert_eq!(Some(65), tree.successor(64));
         assert_eq!(Some(99), tree.successor(65));
         assert_eq!(Some(100000), tree.successor(99999));
     }
 
+    #[test]
+    fn test_pred()
+    {
+        let mut tree = Tree::new(1000000);
+        tree.insert(5);
+        assert_eq!(None, tree.predecessor(5));
+        tree.insert(9);
+        tree.insert(30);
+        tree.insert(64);
+        tree.insert(65);
+        tree.insert(99);
+        tree.insert(99999);
+        tree.insert(100000);
+        assert_eq!(Some(5), tree.predecessor(9));
+        assert_eq!(Some(9), tree.predecessor(30));
+        assert_eq!(Some(30), tree.predecessor(64));
+        assert_eq!(Some(64), tree.predecessor(65));
+        assert_eq!(Some(65), tree.predecessor(99));
+        assert_eq!(Some(99), tree.predecessor(99999));
+        assert_eq!(Some(99999), tree.predecessor(100000));
+        assert_eq!(None, tree.predecessor(0));
+    }
+
+    #[test]
+    fn test_max()
+    {
+        let mut tree = Tree::new(1000000);
+        assert_eq!(None, tree.max());
+        tree.insert(5);
+        assert_eq!(Some(5), tree.max());
+        tree.insert(99999);
+        assert_eq!(Some(99999), tree.max());
+        tree.insert(0);
+        assert_eq!(Some(99999), tree.max());
+    }
+
     #[test]
     fn test_new()
     {
-        let tree = SuccTree::new(1000000);
+        let tree = Tree::new(1000000);
         assert_eq!(tree.tree.len(), 5);
         assert_eq!(tree.tree[0].len(), 15625);
         assert_eq!(tree.tree[1].len(), 245);
@@ -287,7 +1049,7 @@ mod tests {
         assert_eq!(tree.tree[3].len(), 1);
         assert_eq!(tree.tree[4].len(), 1);
 
-        let tree = SuccTree::new(64);
+        let tree = Tree::new(64);
         assert_eq!(tree.tree.len(), 2);
         assert_eq!(tree.tree[0].len(), 1);
         assert_eq!(tree.tree[1].len(), 1);
@@ -296,7 +1058,7 @@ mod tests {
     #[test]
     fn test_insert()
     {
-        let mut tree = SuccTree::new(100);
+        let mut tree = Tree::new(100);
         tree.insert(0);
         assert_eq!(1, tree.tree[0][0]);
         assert_eq!(1, tree.tree[1][0]);
@@ -311,7 +1073,7 @@ mod tests {
     #[test]
     fn test_delete()
     {
-        let mut tree = SuccTree::new(1000000);
+        let mut tree = Tree::new(1000000);
         tree.insert(0);
         assert_eq!(1, tree.tree[0][0]);
         assert_eq!(1, tree.tree[1][0]);
@@ -339,7 +1101,7 @@ mod tests {
     #[test]
     fn test_greater_sibling_in_block()
     {
-        let mut tree = SuccTree::new(1000000);
+        let mut tree = Tree::new(1000000);
         tree.insert(0);
         tree.insert(10);
         tree.insert(50);
@@ -359,7 +1121,7 @@ mod tests {
     #[test]
     fn test_is_empty()
     {
-        let mut tree = SuccTree::new(1000000);
+        let mut tree = Tree::new(1000000);
         assert_eq!(true, tree.is_empty());
         tree.insert(0);
         assert_eq!(false, tree.is_empty());
@@ -368,7 +1130,7 @@ mod tests {
     #[test]
     fn test_min()
     {
-        let mut tree = SuccTree::new(1000000);
+        let mut tree = Tree::new(1000000);
         assert_eq!(None, tree.min());
         tree.insert(5);
         assert_eq!(Some(5), tree.min());